@@ -1,3 +1,5 @@
+use std::path::PathBuf;
+
 use codex_core::protocol::AskForApproval;
 use codex_core::protocol::SandboxPolicy;
 
@@ -44,3 +46,32 @@ pub fn builtin_approval_presets() -> Vec<ApprovalPreset> {
         },
     ]
 }
+
+/// Build the approval preset for running model-issued commands on a remote
+/// host over SSH.
+///
+/// Unlike the built-in presets above, this one cannot be a static constant:
+/// the sandbox it pairs with needs the connection details for the specific
+/// remote host the user is targeting. Approval always stays `OnRequest`
+/// here — a remote host is never implicitly trusted the way a local,
+/// version-controlled workspace can be, regardless of what the user picked
+/// during onboarding for their local directory.
+pub fn remote_ssh_approval_preset(
+    host: String,
+    port: u16,
+    user: String,
+    remote_cwd: PathBuf,
+) -> ApprovalPreset {
+    ApprovalPreset {
+        id: "remote-ssh",
+        label: "远程 SSH",
+        description: "Codex 通过 SSH 在远程主机上运行命令。远程主机不会被自动信任，命令默认需要审批",
+        approval: AskForApproval::OnRequest,
+        sandbox: SandboxPolicy::RemoteSsh {
+            host,
+            port,
+            user,
+            remote_cwd,
+        },
+    }
+}
@@ -1,54 +1,225 @@
 use codex_core::protocol_config_types::ReasoningEffort;
 
 /// A simple preset pairing a model slug with a reasoning effort.
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct ModelPreset {
     /// Stable identifier for the preset.
-    pub id: &'static str,
+    pub id: String,
     /// Display label shown in UIs.
-    pub label: &'static str,
+    pub label: String,
     /// Short human description shown next to the label in UIs.
-    pub description: &'static str,
+    pub description: String,
     /// Model slug (e.g., "gpt-5").
-    pub model: &'static str,
+    pub model: String,
     /// Reasoning effort to apply for this preset.
     pub effort: ReasoningEffort,
 }
 
+/// A `ModelPreset` as read from the Codex config, before validation. Mirrors
+/// `ModelPreset` but with a string `effort` field since config is plain
+/// TOML/JSON and doesn't know about `ReasoningEffort`.
+#[derive(Debug, Clone, serde::Deserialize)]
+pub struct ModelPresetConfig {
+    pub id: String,
+    pub label: String,
+    #[serde(default)]
+    pub description: String,
+    pub model: String,
+    pub effort: String,
+}
+
+/// Error returned when a config-provided model preset fails validation.
+#[derive(Debug, thiserror::Error, PartialEq, Eq)]
+pub enum ModelPresetConfigError {
+    #[error("自定义模型预设 `{id}` 缺少模型名称")]
+    MissingModel { id: String },
+    #[error("自定义模型预设 `{id}` 的 effort 值无效:`{effort}`")]
+    InvalidEffort { id: String, effort: String },
+}
+
+fn parse_effort(id: &str, effort: &str) -> Result<ReasoningEffort, ModelPresetConfigError> {
+    match effort {
+        "minimal" => Ok(ReasoningEffort::Minimal),
+        "low" => Ok(ReasoningEffort::Low),
+        "medium" => Ok(ReasoningEffort::Medium),
+        "high" => Ok(ReasoningEffort::High),
+        other => Err(ModelPresetConfigError::InvalidEffort {
+            id: id.to_string(),
+            effort: other.to_string(),
+        }),
+    }
+}
+
+impl ModelPresetConfig {
+    /// Validate this config entry, converting it into a `ModelPreset`.
+    pub fn into_preset(self) -> Result<ModelPreset, ModelPresetConfigError> {
+        if self.model.trim().is_empty() {
+            return Err(ModelPresetConfigError::MissingModel { id: self.id });
+        }
+        let effort = parse_effort(&self.id, &self.effort)?;
+        Ok(ModelPreset {
+            id: self.id,
+            label: self.label,
+            description: self.description,
+            model: self.model,
+            effort,
+        })
+    }
+}
+
 /// Built-in list of model presets that pair a model with a reasoning effort.
 ///
 /// Keep this UI-agnostic so it can be reused by both TUI and MCP server.
-pub fn builtin_model_presets() -> &'static [ModelPreset] {
+fn builtin_model_presets_vec() -> Vec<ModelPreset> {
     // Order reflects effort from minimal to high.
-    const PRESETS: &[ModelPreset] = &[
+    vec![
         ModelPreset {
-            id: "gpt-5-minimal",
-            label: "gpt-5 minimal",
-            description: "— 响应最快，推理有限；适合编码、指令或轻量任务",
-            model: "gpt-5",
+            id: "gpt-5-minimal".to_string(),
+            label: "gpt-5 minimal".to_string(),
+            description: "— 响应最快，推理有限；适合编码、指令或轻量任务".to_string(),
+            model: "gpt-5".to_string(),
             effort: ReasoningEffort::Minimal,
         },
         ModelPreset {
-            id: "gpt-5-low",
-            label: "gpt-5 low",
-            description: "— 速度与一定推理的平衡；适合简单问题与简短说明",
-            model: "gpt-5",
+            id: "gpt-5-low".to_string(),
+            label: "gpt-5 low".to_string(),
+            description: "— 速度与一定推理的平衡；适合简单问题与简短说明".to_string(),
+            model: "gpt-5".to_string(),
             effort: ReasoningEffort::Low,
         },
         ModelPreset {
-            id: "gpt-5-medium",
-            label: "gpt-5 medium",
-            description: "— 默认设置；在推理深度与延迟之间提供良好平衡，适合通用任务",
-            model: "gpt-5",
+            id: "gpt-5-medium".to_string(),
+            label: "gpt-5 medium".to_string(),
+            description: "— 默认设置；在推理深度与延迟之间提供良好平衡，适合通用任务"
+                .to_string(),
+            model: "gpt-5".to_string(),
             effort: ReasoningEffort::Medium,
         },
         ModelPreset {
-            id: "gpt-5-high",
-            label: "gpt-5 high",
-            description: "— 最大化推理深度，适合复杂或含糊的问题",
-            model: "gpt-5",
+            id: "gpt-5-high".to_string(),
+            label: "gpt-5 high".to_string(),
+            description: "— 最大化推理深度，适合复杂或含糊的问题".to_string(),
+            model: "gpt-5".to_string(),
             effort: ReasoningEffort::High,
         },
-    ];
-    PRESETS
+    ]
+}
+
+/// Built-in list of model presets that pair a model with a reasoning effort.
+///
+/// Keep this UI-agnostic so it can be reused by both TUI and MCP server.
+pub fn builtin_model_presets() -> Vec<ModelPreset> {
+    builtin_model_presets_vec()
+}
+
+/// Merge built-in model presets with user-defined ones from the Codex
+/// config, built-ins first. A custom preset whose `id` matches a built-in
+/// overrides it in place (keeping the built-in's position in the list)
+/// rather than appending a duplicate.
+///
+/// Invalid entries (missing model, unrecognized effort) are skipped; the
+/// returned `Vec<ModelPresetConfigError>` reports what was rejected so
+/// callers can surface it to the user.
+pub fn load_model_presets(
+    custom: Vec<ModelPresetConfig>,
+) -> (Vec<ModelPreset>, Vec<ModelPresetConfigError>) {
+    let mut presets = builtin_model_presets_vec();
+    let mut errors = Vec::new();
+
+    for entry in custom {
+        match entry.into_preset() {
+            Ok(preset) => {
+                if let Some(existing) = presets.iter_mut().find(|p| p.id == preset.id) {
+                    *existing = preset;
+                } else {
+                    presets.push(preset);
+                }
+            }
+            Err(err) => errors.push(err),
+        }
+    }
+
+    (presets, errors)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn load_model_presets_overrides_builtin_by_id() {
+        let custom = vec![ModelPresetConfig {
+            id: "gpt-5-high".to_string(),
+            label: "gpt-5 high (自定义)".to_string(),
+            description: "覆盖内置的 high 预设".to_string(),
+            model: "gpt-5".to_string(),
+            effort: "high".to_string(),
+        }];
+
+        let (presets, errors) = load_model_presets(custom);
+        assert!(errors.is_empty());
+        assert_eq!(presets.len(), 4);
+        let overridden = presets
+            .iter()
+            .find(|p| p.id == "gpt-5-high")
+            .expect("overridden preset should still be present");
+        assert_eq!(overridden.label, "gpt-5 high (自定义)");
+    }
+
+    #[test]
+    fn load_model_presets_appends_new_preset() {
+        let custom = vec![ModelPresetConfig {
+            id: "my-model-medium".to_string(),
+            label: "my-model medium".to_string(),
+            description: "自定义模型预设".to_string(),
+            model: "my-model".to_string(),
+            effort: "medium".to_string(),
+        }];
+
+        let (presets, errors) = load_model_presets(custom);
+        assert!(errors.is_empty());
+        assert_eq!(presets.len(), 5);
+        assert!(presets.iter().any(|p| p.id == "my-model-medium"));
+    }
+
+    #[test]
+    fn load_model_presets_rejects_invalid_effort() {
+        let custom = vec![ModelPresetConfig {
+            id: "bad-effort".to_string(),
+            label: "Bad".to_string(),
+            description: String::new(),
+            model: "my-model".to_string(),
+            effort: "extreme".to_string(),
+        }];
+
+        let (presets, errors) = load_model_presets(custom);
+        assert_eq!(presets.len(), 4);
+        assert_eq!(
+            errors,
+            vec![ModelPresetConfigError::InvalidEffort {
+                id: "bad-effort".to_string(),
+                effort: "extreme".to_string(),
+            }]
+        );
+    }
+
+    #[test]
+    fn load_model_presets_rejects_missing_model() {
+        let custom = vec![ModelPresetConfig {
+            id: "no-model".to_string(),
+            label: "No model".to_string(),
+            description: String::new(),
+            model: "".to_string(),
+            effort: "low".to_string(),
+        }];
+
+        let (presets, errors) = load_model_presets(custom);
+        assert_eq!(presets.len(), 4);
+        assert_eq!(
+            errors,
+            vec![ModelPresetConfigError::MissingModel {
+                id: "no-model".to_string(),
+            }]
+        );
+    }
 }
@@ -8,6 +8,14 @@ use uuid::Uuid;
 
 pub type Result<T> = std::result::Result<T, CodexErr>;
 
+/// Protocol version implemented by this build of Codex. The TUI and MCP
+/// server exchange this in the initial session-configured event so a stale
+/// client talking to a newer server (or vice versa) fails fast with
+/// `CodexErr::VersionMismatch` instead of choking on the first event it
+/// doesn't understand. Bump this whenever the wire protocol changes in a
+/// backwards-incompatible way.
+pub const PROTOCOL_VERSION: u32 = 1;
+
 #[derive(Error, Debug)]
 pub enum SandboxErr {
     /// Error from sandbox execution
@@ -54,6 +62,14 @@ pub enum CodexErr {
     #[error("会话配置事件不是流中的第一个事件")]
     SessionConfiguredNotFirstEvent,
 
+    /// Returned during the initial handshake when the client's
+    /// `PROTOCOL_VERSION` does not match the server's. The session loop
+    /// checks this before the `SessionConfiguredNotFirstEvent` check so a
+    /// mismatched client is rejected immediately rather than after it has
+    /// already sent malformed events.
+    #[error("协议版本不匹配:客户端版本 {client},服务端版本 {server}")]
+    VersionMismatch { client: u32, server: u32 },
+
     /// Returned by run_command_stream when the spawned child process timed out (10s).
     #[error("等待子进程退出超时")]
     Timeout,
@@ -98,6 +114,12 @@ pub enum CodexErr {
     #[error("需要 codex-linux-sandbox 但未提供")]
     LandlockSandboxExecutableNotProvided,
 
+    /// Returned by the remote execution path (e.g. `SandboxPolicy::RemoteSsh`)
+    /// when the SSH transport fails to connect, authenticate, or stream a
+    /// command's output back to the caller.
+    #[error("远程命令执行失败:{0}")]
+    RemoteTransport(String),
+
     // -----------------------------------------------------------------
     // Automatic conversions for common external error types
     // -----------------------------------------------------------------
@@ -161,7 +183,10 @@ impl std::fmt::Display for UsageLimitReachedError {
     }
 }
 
-fn format_reset_duration(total_secs: u64) -> String {
+/// Render a countdown like "3 小时 32 分钟" from a number of seconds. Used
+/// both for the `UsageLimitReachedError` message above and by the TUI's
+/// usage-limit retry status line.
+pub fn format_reset_duration(total_secs: u64) -> String {
     let days = total_secs / 86_400;
     let hours = (total_secs % 86_400) / 3_600;
     let minutes = (total_secs % 3_600) / 60;
@@ -289,6 +314,15 @@ mod tests {
         );
     }
 
+    #[test]
+    fn version_mismatch_formats_client_and_server_versions() {
+        let err = CodexErr::VersionMismatch {
+            client: 1,
+            server: 2,
+        };
+        assert_eq!(err.to_string(), "协议版本不匹配:客户端版本 1,服务端版本 2");
+    }
+
     #[test]
     fn usage_limit_reached_less_than_minute() {
         let err = UsageLimitReachedError {
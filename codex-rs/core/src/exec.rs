@@ -0,0 +1,277 @@
+use std::time::Duration;
+
+use tokio::io::AsyncReadExt;
+use tokio::process::Command;
+
+use crate::error::CodexErr;
+use crate::error::Result;
+use crate::error::SandboxErr;
+use crate::executor::ExecutorFactory;
+use crate::protocol::SandboxPolicy;
+use crate::remote_exec;
+use crate::remote_exec::RemoteExecEvent;
+
+/// One chunk of output from a spawned command, regardless of whether it ran
+/// locally or on a remote host — both paths funnel into this so callers
+/// don't need to care which one ran.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ExecEvent {
+    Stdout(Vec<u8>),
+    Stderr(Vec<u8>),
+    Exit(i32),
+}
+
+impl From<RemoteExecEvent> for ExecEvent {
+    fn from(event: RemoteExecEvent) -> Self {
+        match event {
+            RemoteExecEvent::Stdout(bytes) => ExecEvent::Stdout(bytes),
+            RemoteExecEvent::Stderr(bytes) => ExecEvent::Stderr(bytes),
+            RemoteExecEvent::Exit(code) => ExecEvent::Exit(code),
+        }
+    }
+}
+
+/// Apply `sandbox`'s restrictions to `command` before it is spawned. Local
+/// commands run by `run_command_stream` go through this. The interactive
+/// `/shell` session builds a PTY command rather than a `tokio` `Command`, so
+/// it does not call this; it applies the same network rule through
+/// `network_access_allowed`.
+pub fn apply_sandbox_policy(command: &mut Command, sandbox: &SandboxPolicy) {
+    match sandbox {
+        SandboxPolicy::ReadOnly => {
+            command.env("CODEX_SANDBOX_NETWORK_DISABLED", "1");
+        }
+        SandboxPolicy::WorkspaceWrite { network_access } => {
+            if !network_access {
+                command.env("CODEX_SANDBOX_NETWORK_DISABLED", "1");
+            }
+        }
+        SandboxPolicy::DangerFullAccess => {}
+        SandboxPolicy::RemoteSsh { .. } => {
+            // Remote commands never reach here: `run_command_stream` routes
+            // `SandboxPolicy::RemoteSsh` to `remote_exec` instead of
+            // spawning locally.
+        }
+    }
+}
+
+/// Whether `sandbox` allows the spawned process outbound network access.
+pub fn network_access_allowed(sandbox: &SandboxPolicy) -> bool {
+    match sandbox {
+        SandboxPolicy::ReadOnly => false,
+        SandboxPolicy::WorkspaceWrite { network_access } => *network_access,
+        SandboxPolicy::DangerFullAccess => true,
+        SandboxPolicy::RemoteSsh { .. } => false,
+    }
+}
+
+/// Run `command` under `sandbox`, bounding concurrency via `executor` and
+/// reporting output back through `on_event`.
+///
+/// Routes to the SSH transport when `sandbox` is `SandboxPolicy::RemoteSsh`,
+/// which forwards output chunks as they arrive; otherwise spawns the command
+/// locally, applying `apply_sandbox_policy` first. The local path collects
+/// the output and reports it only after the command has exited: one
+/// `Stdout` and one `Stderr` event (each only if non-empty), then `Exit`. Both paths go through `executor` so a burst of parallel turns —
+/// local or remote — can't exhaust the machine (or open unbounded SSH
+/// sessions on the remote host). `spawn_deadline` only bounds how long a
+/// caller waits for a free permit; once a command is running, neither path
+/// imposes a further deadline on it.
+pub async fn run_command_stream(
+    sandbox: &SandboxPolicy,
+    command: &[String],
+    executor: &ExecutorFactory,
+    spawn_deadline: Duration,
+    mut on_event: impl FnMut(ExecEvent) + Send + 'static,
+) -> Result<i32> {
+    if let SandboxPolicy::RemoteSsh { .. } = sandbox {
+        let sandbox = sandbox.clone();
+        let command = command.to_vec();
+        let handle = executor
+            .spawn(spawn_deadline, async move {
+                remote_exec::run_command_stream_remote(&sandbox, &command, move |event| {
+                    on_event(event.into())
+                })
+                .await
+            })
+            .await?;
+        return handle.join().await.map_err(CodexErr::TokioJoin)?;
+    }
+
+    let Some((program, args)) = command.split_first() else {
+        return Err(CodexErr::Sandbox(SandboxErr::Denied(
+            -1,
+            String::new(),
+            "没有可执行的命令".to_string(),
+        )));
+    };
+
+    let mut cmd = Command::new(program);
+    cmd.args(args);
+    cmd.stdout(std::process::Stdio::piped());
+    cmd.stderr(std::process::Stdio::piped());
+    apply_sandbox_policy(&mut cmd, sandbox);
+
+    let handle = executor
+        .spawn(spawn_deadline, async move {
+            let mut child = cmd.spawn().map_err(|_| CodexErr::Spawn)?;
+            let mut stdout = child.stdout.take().ok_or(CodexErr::Spawn)?;
+            let mut stderr = child.stderr.take().ok_or(CodexErr::Spawn)?;
+
+            // Read both pipes concurrently: draining stdout to completion
+            // before even starting on stderr (or vice versa) deadlocks as
+            // soon as a command writes enough to both to fill the OS pipe
+            // buffer, since the child blocks on the un-drained one while we
+            // sit awaiting EOF on the other.
+            let mut stdout_buf = Vec::new();
+            let mut stderr_buf = Vec::new();
+            let (stdout_result, stderr_result) = tokio::join!(
+                stdout.read_to_end(&mut stdout_buf),
+                stderr.read_to_end(&mut stderr_buf)
+            );
+            stdout_result.map_err(CodexErr::Io)?;
+            stderr_result.map_err(CodexErr::Io)?;
+
+            let status = child.wait().await.map_err(CodexErr::Io)?;
+            Ok::<_, CodexErr>((stdout_buf, stderr_buf, status.code().unwrap_or(-1)))
+        })
+        .await?;
+
+    let (stdout_buf, stderr_buf, exit_code) =
+        handle.join().await.map_err(CodexErr::TokioJoin)??;
+
+    if !stdout_buf.is_empty() {
+        on_event(ExecEvent::Stdout(stdout_buf));
+    }
+    if !stderr_buf.is_empty() {
+        on_event(ExecEvent::Stderr(stderr_buf));
+    }
+    on_event(ExecEvent::Exit(exit_code));
+
+    Ok(exit_code)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn network_access_allowed_matches_policy() {
+        assert!(!network_access_allowed(&SandboxPolicy::ReadOnly));
+        assert!(!network_access_allowed(&SandboxPolicy::WorkspaceWrite {
+            network_access: false
+        }));
+        assert!(network_access_allowed(&SandboxPolicy::WorkspaceWrite {
+            network_access: true
+        }));
+        assert!(network_access_allowed(&SandboxPolicy::DangerFullAccess));
+    }
+
+    #[tokio::test]
+    async fn run_command_stream_reports_stdout_and_exit_code() {
+        let executor = ExecutorFactory::new(2);
+        let events = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+        let sink = events.clone();
+        let exit_code = run_command_stream(
+            &SandboxPolicy::DangerFullAccess,
+            &["echo".to_string(), "hello".to_string()],
+            &executor,
+            Duration::from_secs(5),
+            move |event| sink.lock().unwrap().push(event),
+        )
+        .await
+        .expect("echo should run successfully");
+
+        assert_eq!(exit_code, 0);
+        assert_eq!(
+            *events.lock().unwrap(),
+            vec![ExecEvent::Stdout(b"hello\n".to_vec()), ExecEvent::Exit(0)]
+        );
+    }
+
+    #[tokio::test]
+    async fn run_command_stream_drains_stdout_and_stderr_concurrently() {
+        // Write enough to both streams to fill the OS pipe buffer (commonly
+        // 64KiB on Linux) on each before either is drained. Sequential
+        // `read_to_end` calls deadlock here: the child blocks writing to
+        // the un-drained stream while we're still awaiting EOF on the
+        // other. Bound the run with a timeout so a regression fails this
+        // test instead of hanging the suite.
+        let executor = ExecutorFactory::new(1);
+        // Two background jobs write to stdout and stderr concurrently, then
+        // `wait` blocks until both finish. The shell process itself holds
+        // both pipe write-ends open until `wait` returns, so neither stream
+        // reaches EOF until the other is drained too.
+        let script = "yes out | head -c 200000 & yes err | head -c 200000 1>&2 & wait";
+        let events = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+        let sink = events.clone();
+        let exit_code = tokio::time::timeout(
+            Duration::from_secs(10),
+            run_command_stream(
+                &SandboxPolicy::DangerFullAccess,
+                &["sh".to_string(), "-c".to_string(), script.to_string()],
+                &executor,
+                Duration::from_secs(5),
+                move |event| sink.lock().unwrap().push(event),
+            ),
+        )
+        .await
+        .expect("draining both streams concurrently should not deadlock")
+        .expect("script should run successfully");
+
+        assert_eq!(exit_code, 0);
+        let events = events.lock().unwrap();
+        let stdout_len: usize = events
+            .iter()
+            .filter_map(|event| match event {
+                ExecEvent::Stdout(bytes) => Some(bytes.len()),
+                _ => None,
+            })
+            .sum();
+        let stderr_len: usize = events
+            .iter()
+            .filter_map(|event| match event {
+                ExecEvent::Stderr(bytes) => Some(bytes.len()),
+                _ => None,
+            })
+            .sum();
+        assert_eq!(stdout_len, 200_000);
+        assert_eq!(stderr_len, 200_000);
+    }
+
+    #[tokio::test]
+    async fn run_command_stream_rejects_empty_command() {
+        let executor = ExecutorFactory::new(1);
+        let err = run_command_stream(
+            &SandboxPolicy::DangerFullAccess,
+            &[],
+            &executor,
+            Duration::from_secs(1),
+            |_| {},
+        )
+        .await
+        .expect_err("an empty command must be rejected");
+        assert!(matches!(err, CodexErr::Sandbox(SandboxErr::Denied(..))));
+    }
+
+    #[tokio::test]
+    async fn run_command_stream_routes_remote_policy_to_ssh_path() {
+        let executor = ExecutorFactory::new(1);
+        let sandbox = SandboxPolicy::RemoteSsh {
+            host: String::new(),
+            port: 22,
+            user: "codex".to_string(),
+            remote_cwd: std::path::PathBuf::from("/tmp"),
+        };
+        let err = run_command_stream(
+            &sandbox,
+            &["echo".to_string()],
+            &executor,
+            Duration::from_millis(50),
+            |_| {},
+        )
+        .await
+        .expect_err("an empty host must still fail through the remote path");
+        assert!(matches!(err, CodexErr::RemoteTransport(_)));
+    }
+}
@@ -0,0 +1,117 @@
+use std::future::Future;
+use std::sync::Arc;
+use std::time::Duration;
+
+use tokio::sync::OwnedSemaphorePermit;
+use tokio::sync::Semaphore;
+use tokio::task::JoinError;
+use tokio::task::JoinHandle;
+
+use crate::error::CodexErr;
+use crate::error::Result;
+
+/// Bounds how many sandboxed commands or parallel turns may run at once.
+///
+/// `ExecutorFactory` is cheap to clone: clones share the same underlying
+/// `Semaphore`, so `run_command_stream` can hand a factory to each caller
+/// without threading a global limiter through every call site.
+#[derive(Clone)]
+pub struct ExecutorFactory {
+    semaphore: Arc<Semaphore>,
+}
+
+/// A spawned command/turn bundled with the permit that reserved its slot.
+///
+/// The permit is released when this handle is dropped (or consumed by
+/// [`ExecutorHandle::join`]), so the caller never has to remember to give
+/// the slot back explicitly.
+pub struct ExecutorHandle<T> {
+    task: JoinHandle<T>,
+    _permit: OwnedSemaphorePermit,
+}
+
+impl ExecutorFactory {
+    /// Create a factory that allows at most `max_concurrency` tasks to run
+    /// at the same time. Values of `0` are treated as `1`.
+    ///
+    /// A slot is held by the returned `ExecutorHandle`, not by the task:
+    /// dropping a handle before its task finishes frees the slot while the
+    /// task keeps running.
+    pub fn new(max_concurrency: usize) -> Self {
+        Self {
+            semaphore: Arc::new(Semaphore::new(max_concurrency.max(1))),
+        }
+    }
+
+    /// Acquire a permit and spawn `future`, returning a handle that holds
+    /// the permit until the handle is joined or dropped.
+    ///
+    /// If no permit becomes available within `deadline`, the future is never
+    /// spawned and this returns `CodexErr::Timeout`.
+    pub async fn spawn<F>(
+        &self,
+        deadline: Duration,
+        future: F,
+    ) -> Result<ExecutorHandle<F::Output>>
+    where
+        F: Future + Send + 'static,
+        F::Output: Send + 'static,
+    {
+        let permit = tokio::time::timeout(deadline, self.semaphore.clone().acquire_owned())
+            .await
+            .map_err(|_| CodexErr::Timeout)?
+            .expect("ExecutorFactory semaphore is never closed");
+
+        Ok(ExecutorHandle {
+            task: tokio::spawn(future),
+            _permit: permit,
+        })
+    }
+}
+
+impl<T> ExecutorHandle<T> {
+    /// Wait for the underlying task to finish, returning its output.
+    pub async fn join(self) -> std::result::Result<T, JoinError> {
+        self.task.await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn spawn_releases_permit_after_completion() {
+        let factory = ExecutorFactory::new(1);
+
+        let first = factory
+            .spawn(Duration::from_secs(1), async { 1 + 1 })
+            .await
+            .expect("first spawn should acquire the only permit");
+        assert_eq!(first.join().await.expect("task should not panic"), 2);
+
+        // The permit from `first` has been released, so a second spawn
+        // should succeed immediately rather than waiting out the deadline.
+        let second = factory
+            .spawn(Duration::from_millis(50), async { 2 + 2 })
+            .await
+            .expect("permit should be available again after first completed");
+        assert_eq!(second.join().await.expect("task should not panic"), 4);
+    }
+
+    #[tokio::test]
+    async fn spawn_times_out_when_no_permit_is_free() {
+        let factory = ExecutorFactory::new(1);
+        let _held = factory
+            .spawn(Duration::from_secs(1), async {
+                tokio::time::sleep(Duration::from_secs(1)).await;
+            })
+            .await
+            .expect("first spawn should acquire the only permit");
+
+        let result = factory
+            .spawn(Duration::from_millis(10), async { 0 })
+            .await;
+        assert!(matches!(result, Err(CodexErr::Timeout)));
+    }
+}
@@ -0,0 +1,42 @@
+use std::path::PathBuf;
+
+/// Determines when Codex pauses a turn to ask the user for approval before
+/// running a command or applying a patch.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AskForApproval {
+    /// Ask before anything that falls outside the current `SandboxPolicy`.
+    OnRequest,
+    /// Never ask; run everything the sandbox allows without interruption.
+    Never,
+}
+
+/// Determines what commands Codex is allowed to run and where.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SandboxPolicy {
+    /// Commands may read files but not write them or reach the network.
+    ReadOnly,
+    /// Commands may read and write within the workspace; network access is
+    /// configurable.
+    WorkspaceWrite { network_access: bool },
+    /// No restrictions. Use with care.
+    DangerFullAccess,
+    /// Commands run on a remote host over SSH instead of locally. The
+    /// target directory (`remote_cwd`) is never implicitly trusted the way
+    /// a local workspace can be (see `TrustDirectoryWidget`).
+    RemoteSsh {
+        host: String,
+        port: u16,
+        user: String,
+        remote_cwd: PathBuf,
+    },
+}
+
+impl SandboxPolicy {
+    /// The default workspace-write policy: read/write within the workspace,
+    /// network access disabled.
+    pub fn new_workspace_write_policy() -> Self {
+        SandboxPolicy::WorkspaceWrite {
+            network_access: false,
+        }
+    }
+}
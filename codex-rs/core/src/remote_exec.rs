@@ -0,0 +1,356 @@
+use std::io::Read;
+use std::net::TcpStream;
+use std::path::Path;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use ssh2::ExtendedData;
+use ssh2::Session;
+
+use crate::error::CodexErr;
+use crate::error::Result;
+use crate::protocol::SandboxPolicy;
+
+/// One chunk of output streamed back from a remote command, mirroring the
+/// shape `run_command_stream` already emits for local commands so callers
+/// can treat both uniformly.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RemoteExecEvent {
+    Stdout(Vec<u8>),
+    Stderr(Vec<u8>),
+    Exit(i32),
+}
+
+/// Open an SSH channel described by `sandbox` and run `command` on the
+/// remote host, streaming its output back via `on_event`.
+///
+/// Connection failures (bad host, invalid port, refused connection, auth
+/// failure) surface as `CodexErr::RemoteTransport` rather than panicking or
+/// hanging the turn. The actual SSH I/O is blocking (`ssh2`), so it runs on
+/// the blocking thread pool via `spawn_blocking` rather than on the async
+/// executor.
+///
+/// This deliberately does not impose its own deadline on connect+exec: the
+/// caller (`run_command_stream`) already bounds how long it waits for an
+/// `ExecutorFactory` permit before dispatching here, and once dispatched a
+/// remote command is subject to the same "runs until it finishes" rule as a
+/// local one.
+pub async fn run_command_stream_remote(
+    sandbox: &SandboxPolicy,
+    command: &[String],
+    mut on_event: impl FnMut(RemoteExecEvent) + Send + 'static,
+) -> Result<i32> {
+    let SandboxPolicy::RemoteSsh {
+        host,
+        port,
+        user,
+        remote_cwd,
+    } = sandbox
+    else {
+        return Err(CodexErr::RemoteTransport(
+            "run_command_stream_remote 需要 SandboxPolicy::RemoteSsh".to_string(),
+        ));
+    };
+    if command.is_empty() {
+        return Err(CodexErr::RemoteTransport("没有可执行的远程命令".to_string()));
+    }
+
+    let host = host.clone();
+    let port = *port;
+    let user = user.clone();
+    let remote_cwd = remote_cwd.clone();
+    let command = command.to_vec();
+
+    // ssh2's API is blocking; stream events out through an unbounded
+    // channel so the caller still sees them live rather than all at once
+    // after the blocking task finishes.
+    let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+    let exec = tokio::task::spawn_blocking(move || {
+        exec_over_ssh(&host, port, &user, &remote_cwd, &command, |event| {
+            let _ = tx.send(event);
+        })
+    });
+
+    let forward_events = async {
+        while let Some(event) = rx.recv().await {
+            on_event(event);
+        }
+    };
+
+    let (exec_result, ()) = tokio::join!(exec, forward_events);
+
+    exec_result.map_err(|e| CodexErr::RemoteTransport(format!("远程执行任务异常终止:{e}")))?
+}
+
+/// Blocking SSH session setup + command execution. Runs on a blocking
+/// thread; see `run_command_stream_remote` for the async wrapper.
+fn exec_over_ssh(
+    host: &str,
+    port: u16,
+    user: &str,
+    remote_cwd: &Path,
+    command: &[String],
+    mut on_event: impl FnMut(RemoteExecEvent),
+) -> Result<i32> {
+    if host.trim().is_empty() {
+        return Err(CodexErr::RemoteTransport("远程主机地址不能为空".to_string()));
+    }
+    if port == 0 {
+        return Err(CodexErr::RemoteTransport(format!("无效的端口:{port}")));
+    }
+    if user.trim().is_empty() {
+        return Err(CodexErr::RemoteTransport("远程用户名不能为空".to_string()));
+    }
+
+    let tcp = TcpStream::connect((host, port))
+        .map_err(|e| CodexErr::RemoteTransport(format!("无法连接到 {host}:{port}:{e}")))?;
+
+    let mut session = Session::new()
+        .map_err(|e| CodexErr::RemoteTransport(format!("创建 SSH 会话失败:{e}")))?;
+    session.set_tcp_stream(tcp);
+    session
+        .handshake()
+        .map_err(|e| CodexErr::RemoteTransport(format!("SSH 握手失败:{e}")))?;
+
+    session
+        .userauth_agent(user)
+        .map_err(|e| CodexErr::RemoteTransport(format!("SSH 认证失败(ssh-agent):{e}")))?;
+    if !session.authenticated() {
+        return Err(CodexErr::RemoteTransport(
+            "SSH 认证失败:ssh-agent 未提供可用身份".to_string(),
+        ));
+    }
+
+    let mut channel = session
+        .channel_session()
+        .map_err(|e| CodexErr::RemoteTransport(format!("打开 SSH 通道失败:{e}")))?;
+    // Keep stdout and stderr as two separate streams (the library default,
+    // made explicit here) rather than merging stderr into stdout, since
+    // `on_event` needs to tell them apart.
+    channel
+        .handle_extended_data(ExtendedData::Normal)
+        .map_err(|e| CodexErr::RemoteTransport(format!("配置远程 stderr 分离失败:{e}")))?;
+
+    let remote_command = build_remote_command(remote_cwd, command);
+    channel
+        .exec(&remote_command)
+        .map_err(|e| CodexErr::RemoteTransport(format!("执行远程命令失败:{e}")))?;
+
+    // Switch the session to non-blocking reads before draining output: a
+    // blocking `channel.read` on one stream would stall if the remote
+    // process is blocked writing to the *other* stream (e.g. its stderr
+    // pipe is full while it has nothing queued on stdout yet), since we
+    // wouldn't get to the stderr read that unblocks it until the stdout
+    // read returns. Non-blocking reads let `drain_streams` poll both every
+    // iteration instead of waiting on either exclusively.
+    session.set_blocking(false);
+    // All three closures need the channel; each borrows it only for the
+    // length of one call, so share it through a `RefCell`.
+    let shared_channel = std::cell::RefCell::new(&mut channel);
+    let drain_result = drain_streams(
+        |buf| shared_channel.borrow_mut().read(buf),
+        |buf| shared_channel.borrow_mut().stderr().read(buf),
+        || shared_channel.borrow_mut().eof(),
+        &mut on_event,
+    );
+    session.set_blocking(true);
+    drain_result?;
+
+    channel
+        .wait_close()
+        .map_err(|e| CodexErr::RemoteTransport(format!("等待远程命令退出失败:{e}")))?;
+    let exit_status = channel
+        .exit_status()
+        .map_err(|e| CodexErr::RemoteTransport(format!("获取远程退出码失败:{e}")))?;
+
+    on_event(RemoteExecEvent::Exit(exit_status));
+    Ok(exit_status)
+}
+
+/// Poll `stdout_read` and `stderr_read` in lockstep until `is_eof` reports
+/// the channel is done, emitting each chunk via `on_event` as it arrives.
+///
+/// Pulled out of `exec_over_ssh` so the alternation logic — the part
+/// responsible for not letting a blocking read on one stream starve the
+/// other — can be unit tested against a fake transport instead of a live
+/// SSH channel. Both read closures are expected to return
+/// `ErrorKind::WouldBlock` rather than block when no data is available yet.
+fn drain_streams(
+    mut stdout_read: impl FnMut(&mut [u8]) -> std::io::Result<usize>,
+    mut stderr_read: impl FnMut(&mut [u8]) -> std::io::Result<usize>,
+    mut is_eof: impl FnMut() -> bool,
+    on_event: &mut impl FnMut(RemoteExecEvent),
+) -> Result<()> {
+    let mut stdout_buf = [0u8; 8192];
+    let mut stderr_buf = [0u8; 8192];
+    loop {
+        let mut made_progress = false;
+
+        match stdout_read(&mut stdout_buf) {
+            Ok(0) => {}
+            Ok(n) => {
+                on_event(RemoteExecEvent::Stdout(stdout_buf[..n].to_vec()));
+                made_progress = true;
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => {}
+            Err(e) => return Err(CodexErr::RemoteTransport(format!("读取远程 stdout 失败:{e}"))),
+        }
+
+        match stderr_read(&mut stderr_buf) {
+            Ok(0) => {}
+            Ok(n) => {
+                on_event(RemoteExecEvent::Stderr(stderr_buf[..n].to_vec()));
+                made_progress = true;
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => {}
+            Err(e) => return Err(CodexErr::RemoteTransport(format!("读取远程 stderr 失败:{e}"))),
+        }
+
+        if is_eof() {
+            return Ok(());
+        }
+        if !made_progress {
+            // Neither stream had data ready; avoid busy-spinning the
+            // blocking thread while we wait for more.
+            std::thread::sleep(Duration::from_millis(5));
+        }
+    }
+}
+
+/// Build the single shell command line sent over the SSH channel: `cd` into
+/// `remote_cwd`, then run `command`, each argument individually quoted so
+/// e.g. filenames with spaces survive the remote shell's word-splitting.
+fn build_remote_command(remote_cwd: &Path, command: &[String]) -> String {
+    let quoted_cwd = shell_quote(&remote_cwd.display().to_string());
+    let quoted_command: Vec<String> = command.iter().map(|arg| shell_quote(arg)).collect();
+    format!("cd {quoted_cwd} && {}", quoted_command.join(" "))
+}
+
+fn shell_quote(s: &str) -> String {
+    format!("'{}'", s.replace('\'', "'\\''"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn remote_sandbox() -> SandboxPolicy {
+        SandboxPolicy::RemoteSsh {
+            host: "example.com".to_string(),
+            port: 22,
+            user: "codex".to_string(),
+            remote_cwd: PathBuf::from("/home/codex"),
+        }
+    }
+
+    #[test]
+    fn build_remote_command_quotes_cwd_and_args() {
+        let cwd = PathBuf::from("/home/codex/my project");
+        let command = vec!["echo".to_string(), "it's fine".to_string()];
+        assert_eq!(
+            build_remote_command(&cwd, &command),
+            "cd '/home/codex/my project' && 'echo' 'it'\\''s fine'"
+        );
+    }
+
+    #[tokio::test]
+    async fn rejects_non_remote_sandbox_policies() {
+        let err = run_command_stream_remote(&SandboxPolicy::ReadOnly, &["echo".to_string()], |_| {})
+            .await
+            .expect_err("a non-remote sandbox policy must be rejected");
+        assert!(matches!(err, CodexErr::RemoteTransport(_)));
+    }
+
+    #[tokio::test]
+    async fn rejects_empty_command() {
+        let err = run_command_stream_remote(&remote_sandbox(), &[], |_| {})
+            .await
+            .expect_err("an empty command must be rejected");
+        assert!(matches!(err, CodexErr::RemoteTransport(_)));
+    }
+
+    #[tokio::test]
+    async fn rejects_empty_host() {
+        let sandbox = SandboxPolicy::RemoteSsh {
+            host: "".to_string(),
+            port: 22,
+            user: "codex".to_string(),
+            remote_cwd: PathBuf::from("/home/codex"),
+        };
+        let err = run_command_stream_remote(&sandbox, &["echo".to_string()], |_| {})
+            .await
+            .expect_err("an empty host must be rejected");
+        assert!(matches!(err, CodexErr::RemoteTransport(_)));
+    }
+
+    #[tokio::test]
+    async fn rejects_invalid_port() {
+        let sandbox = SandboxPolicy::RemoteSsh {
+            host: "example.com".to_string(),
+            port: 0,
+            user: "codex".to_string(),
+            remote_cwd: PathBuf::from("/home/codex"),
+        };
+        let err = run_command_stream_remote(&sandbox, &["echo".to_string()], |_| {})
+            .await
+            .expect_err("port 0 must be rejected");
+        assert!(matches!(err, CodexErr::RemoteTransport(_)));
+    }
+
+    #[tokio::test]
+    async fn surfaces_connection_failure_as_remote_transport_error() {
+        // Port 1 on localhost should refuse the connection immediately
+        // rather than actually reach a real SSH server.
+        let sandbox = SandboxPolicy::RemoteSsh {
+            host: "127.0.0.1".to_string(),
+            port: 1,
+            user: "codex".to_string(),
+            remote_cwd: PathBuf::from("/tmp"),
+        };
+        let err = run_command_stream_remote(&sandbox, &["echo".to_string(), "hi".to_string()], |_| {})
+            .await
+            .expect_err("connecting to a closed port must fail, not silently succeed");
+        assert!(matches!(err, CodexErr::RemoteTransport(_)));
+    }
+
+    #[test]
+    fn drain_streams_delivers_stderr_while_stdout_is_would_block() {
+        // A fake transport where stdout reports `WouldBlock` for the first
+        // couple of polls while stderr already has data queued. A blocking
+        // `channel.read` on stdout alone would stall here until stdout
+        // caught up; draining both every iteration must still deliver the
+        // stderr bytes right away instead of waiting on stdout.
+        let stdout_polls = std::cell::Cell::new(0);
+        let stderr_delivered = std::cell::Cell::new(false);
+        let mut events = Vec::new();
+
+        drain_streams(
+            |_buf| {
+                stdout_polls.set(stdout_polls.get() + 1);
+                if stdout_polls.get() <= 2 {
+                    Err(std::io::Error::from(std::io::ErrorKind::WouldBlock))
+                } else {
+                    Ok(0)
+                }
+            },
+            |buf| {
+                if stderr_delivered.get() {
+                    Ok(0)
+                } else {
+                    stderr_delivered.set(true);
+                    buf[..5].copy_from_slice(b"hello");
+                    Ok(5)
+                }
+            },
+            || stdout_polls.get() > 2 && stderr_delivered.get(),
+            &mut |event| events.push(event),
+        )
+        .expect("draining a fake transport should not fail");
+
+        assert!(
+            stderr_delivered.get(),
+            "stderr must be drained even while stdout is still WouldBlock"
+        );
+        assert_eq!(events, vec![RemoteExecEvent::Stderr(b"hello".to_vec())]);
+    }
+}
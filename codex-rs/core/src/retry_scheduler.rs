@@ -0,0 +1,164 @@
+use std::time::Duration;
+
+use crate::error::CodexErr;
+use crate::error::Result;
+use crate::error::UsageLimitReachedError;
+
+/// Default ceiling on how long the scheduler will wait before giving up and
+/// surfacing the original error, unless the caller opts into a longer wait
+/// via `UsageLimitRetryConfig::max_wait`.
+const DEFAULT_MAX_WAIT: Duration = Duration::from_secs(60 * 60); // 1 hour
+
+/// Opt-in configuration for automatically retrying a turn that failed with
+/// `CodexErr::UsageLimitReached`, reusing the same transient-retry idea
+/// already used for `CodexErr::Stream(_, Option<Duration>)`.
+#[derive(Debug, Clone, Copy)]
+pub struct UsageLimitRetryConfig {
+    /// Whether the scheduler is enabled at all.
+    pub enabled: bool,
+    /// Refuse to sleep longer than this even if `resets_in_seconds` reports
+    /// a longer wait (e.g. a multi-day reset window). `None` means use
+    /// `DEFAULT_MAX_WAIT`.
+    pub max_wait: Option<Duration>,
+}
+
+impl Default for UsageLimitRetryConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            max_wait: None,
+        }
+    }
+}
+
+/// Decides whether a turn that failed with `CodexErr::UsageLimitReached`
+/// should be parked and automatically resubmitted, and for how long.
+pub struct UsageLimitRetryScheduler {
+    config: UsageLimitRetryConfig,
+}
+
+/// Outcome of checking a `CodexErr::UsageLimitReached` against the scheduler.
+pub enum RetryDecision {
+    /// Sleep for this long, then resubmit the turn.
+    RetryAfter(Duration),
+    /// Do not retry automatically; surface the original error.
+    GiveUp,
+}
+
+impl UsageLimitRetryScheduler {
+    pub fn new(config: UsageLimitRetryConfig) -> Self {
+        Self { config }
+    }
+
+    /// Decide what to do with a usage-limit error, given how long the model
+    /// said the limit takes to reset.
+    pub fn decide(&self, error: &UsageLimitReachedError) -> RetryDecision {
+        if !self.config.enabled {
+            return RetryDecision::GiveUp;
+        }
+
+        let Some(resets_in_seconds) = error.resets_in_seconds else {
+            return RetryDecision::GiveUp;
+        };
+
+        let max_wait = self.config.max_wait.unwrap_or(DEFAULT_MAX_WAIT);
+        let wait = Duration::from_secs(resets_in_seconds);
+        if wait > max_wait {
+            return RetryDecision::GiveUp;
+        }
+
+        RetryDecision::RetryAfter(wait)
+    }
+
+    /// Sleep for `wait`, returning `CodexErr::Interrupted` instead of
+    /// `Ok(())` if `cancel` resolves first (e.g. the user pressed Ctrl-C).
+    ///
+    /// Callers are expected to emit a TUI status line counting down using
+    /// the existing `format_reset_duration` helper while this future is
+    /// pending.
+    pub async fn wait_or_cancel(
+        wait: Duration,
+        cancel: impl std::future::Future<Output = ()>,
+    ) -> Result<()> {
+        tokio::select! {
+            _ = tokio::time::sleep(wait) => Ok(()),
+            _ = cancel => Err(CodexErr::Interrupted),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn error_with_reset(resets_in_seconds: Option<u64>) -> UsageLimitReachedError {
+        UsageLimitReachedError {
+            plan_type: None,
+            resets_in_seconds,
+        }
+    }
+
+    #[test]
+    fn disabled_scheduler_gives_up() {
+        let scheduler = UsageLimitRetryScheduler::new(UsageLimitRetryConfig {
+            enabled: false,
+            max_wait: None,
+        });
+        assert!(matches!(
+            scheduler.decide(&error_with_reset(Some(60))),
+            RetryDecision::GiveUp
+        ));
+    }
+
+    #[test]
+    fn enabled_scheduler_retries_when_within_max_wait() {
+        let scheduler = UsageLimitRetryScheduler::new(UsageLimitRetryConfig {
+            enabled: true,
+            max_wait: Some(Duration::from_secs(120)),
+        });
+        match scheduler.decide(&error_with_reset(Some(60))) {
+            RetryDecision::RetryAfter(wait) => assert_eq!(wait, Duration::from_secs(60)),
+            RetryDecision::GiveUp => panic!("expected a retry decision"),
+        }
+    }
+
+    #[test]
+    fn enabled_scheduler_refuses_multi_day_waits_beyond_max() {
+        let scheduler = UsageLimitRetryScheduler::new(UsageLimitRetryConfig {
+            enabled: true,
+            max_wait: Some(Duration::from_secs(3600)),
+        });
+        assert!(matches!(
+            scheduler.decide(&error_with_reset(Some(2 * 86_400))),
+            RetryDecision::GiveUp
+        ));
+    }
+
+    #[test]
+    fn enabled_scheduler_gives_up_without_reset_estimate() {
+        let scheduler = UsageLimitRetryScheduler::new(UsageLimitRetryConfig {
+            enabled: true,
+            max_wait: None,
+        });
+        assert!(matches!(
+            scheduler.decide(&error_with_reset(None)),
+            RetryDecision::GiveUp
+        ));
+    }
+
+    #[tokio::test]
+    async fn wait_or_cancel_resolves_ok_after_sleep() {
+        let result = UsageLimitRetryScheduler::wait_or_cancel(Duration::from_millis(1), async {
+            tokio::time::sleep(Duration::from_secs(10)).await;
+        })
+        .await;
+        assert!(result.is_ok());
+    }
+
+    #[tokio::test]
+    async fn wait_or_cancel_interrupted_when_cancelled_first() {
+        let result = UsageLimitRetryScheduler::wait_or_cancel(Duration::from_secs(10), async {})
+            .await;
+        assert!(matches!(result, Err(CodexErr::Interrupted)));
+    }
+}
@@ -0,0 +1,107 @@
+use crate::error::CodexErr;
+use crate::error::Result;
+use crate::error::PROTOCOL_VERSION;
+
+/// Enforces the two invariants a client's event stream must satisfy before
+/// the session loop starts trusting anything it sends:
+///
+/// 1. The first event must be `session_configured`.
+/// 2. That event's protocol version must match `PROTOCOL_VERSION`.
+///
+/// The version check runs *before* the "must be first" check, so a stale
+/// client fails fast with an actionable `VersionMismatch` instead of the
+/// more confusing `SessionConfiguredNotFirstEvent` it would otherwise hit
+/// after the handshake itself has already gone wrong.
+#[derive(Debug, Default)]
+pub struct SessionHandshake {
+    session_configured_seen: bool,
+}
+
+impl SessionHandshake {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Validate an incoming `session_configured` event from the client.
+    pub fn on_session_configured(&mut self, client_protocol_version: u32) -> Result<()> {
+        if client_protocol_version != PROTOCOL_VERSION {
+            return Err(CodexErr::VersionMismatch {
+                client: client_protocol_version,
+                server: PROTOCOL_VERSION,
+            });
+        }
+
+        if self.session_configured_seen {
+            return Err(CodexErr::SessionConfiguredNotFirstEvent);
+        }
+
+        self.session_configured_seen = true;
+        Ok(())
+    }
+
+    /// Validate any other event from the client, which is only legal once
+    /// `session_configured` has already been accepted.
+    pub fn on_other_event(&self) -> Result<()> {
+        if self.session_configured_seen {
+            Ok(())
+        } else {
+            Err(CodexErr::SessionConfiguredNotFirstEvent)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matching_version_accepts_first_session_configured_event() {
+        let mut handshake = SessionHandshake::new();
+        assert!(handshake.on_session_configured(PROTOCOL_VERSION).is_ok());
+        assert!(handshake.on_other_event().is_ok());
+    }
+
+    #[test]
+    fn mismatched_version_is_rejected_even_before_ordering_is_checked() {
+        // A second `session_configured` with a bad version should still
+        // report VersionMismatch, not SessionConfiguredNotFirstEvent, since
+        // the version check runs first.
+        let mut handshake = SessionHandshake::new();
+        handshake
+            .on_session_configured(PROTOCOL_VERSION)
+            .expect("first session_configured should be accepted");
+
+        let err = handshake
+            .on_session_configured(PROTOCOL_VERSION + 1)
+            .expect_err("mismatched version must be rejected");
+        assert!(matches!(
+            err,
+            CodexErr::VersionMismatch {
+                client,
+                server
+            } if client == PROTOCOL_VERSION + 1 && server == PROTOCOL_VERSION
+        ));
+    }
+
+    #[test]
+    fn event_before_session_configured_is_rejected() {
+        let handshake = SessionHandshake::new();
+        let err = handshake
+            .on_other_event()
+            .expect_err("events before session_configured must be rejected");
+        assert!(matches!(err, CodexErr::SessionConfiguredNotFirstEvent));
+    }
+
+    #[test]
+    fn duplicate_session_configured_with_correct_version_is_rejected() {
+        let mut handshake = SessionHandshake::new();
+        handshake
+            .on_session_configured(PROTOCOL_VERSION)
+            .expect("first session_configured should be accepted");
+
+        let err = handshake
+            .on_session_configured(PROTOCOL_VERSION)
+            .expect_err("a second session_configured event must be rejected");
+        assert!(matches!(err, CodexErr::SessionConfiguredNotFirstEvent));
+    }
+}
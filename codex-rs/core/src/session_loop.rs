@@ -0,0 +1,90 @@
+use crate::error::Result;
+use crate::session_handshake::SessionHandshake;
+
+/// The subset of incoming client events the session loop's handshake cares
+/// about. The full protocol event enum (exec output, approvals, etc.) is
+/// unaffected by this change; this is only what `SessionLoop::on_client_event`
+/// needs in order to enforce the handshake ahead of everything else.
+#[derive(Debug, Clone)]
+pub enum ClientEvent {
+    SessionConfigured { protocol_version: u32 },
+    Other,
+}
+
+/// Drives the per-connection handshake at the front of the session loop.
+///
+/// Every client event passes through `on_client_event` before the rest of
+/// the loop (turn execution, approvals, etc.) is allowed to see it, so a
+/// version mismatch or an out-of-order `session_configured` is rejected
+/// immediately instead of surfacing as confusing downstream behavior.
+pub struct SessionLoop {
+    handshake: SessionHandshake,
+}
+
+impl SessionLoop {
+    pub fn new() -> Self {
+        Self {
+            handshake: SessionHandshake::new(),
+        }
+    }
+
+    /// Validate `event` against the handshake. Callers must reject the
+    /// connection — without running any further session logic — as soon as
+    /// this returns `Err`.
+    pub fn on_client_event(&mut self, event: &ClientEvent) -> Result<()> {
+        match event {
+            ClientEvent::SessionConfigured { protocol_version } => {
+                self.handshake.on_session_configured(*protocol_version)
+            }
+            ClientEvent::Other => self.handshake.on_other_event(),
+        }
+    }
+}
+
+impl Default for SessionLoop {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::CodexErr;
+    use crate::error::PROTOCOL_VERSION;
+
+    #[test]
+    fn accepts_matching_version_then_other_events() {
+        let mut session_loop = SessionLoop::new();
+        assert!(session_loop
+            .on_client_event(&ClientEvent::SessionConfigured {
+                protocol_version: PROTOCOL_VERSION
+            })
+            .is_ok());
+        assert!(session_loop.on_client_event(&ClientEvent::Other).is_ok());
+    }
+
+    #[test]
+    fn rejects_mismatched_version_as_the_very_first_event() {
+        let mut session_loop = SessionLoop::new();
+        let err = session_loop
+            .on_client_event(&ClientEvent::SessionConfigured {
+                protocol_version: PROTOCOL_VERSION + 1,
+            })
+            .expect_err("a stale client must be rejected immediately");
+        assert!(matches!(
+            err,
+            CodexErr::VersionMismatch { client, server }
+                if client == PROTOCOL_VERSION + 1 && server == PROTOCOL_VERSION
+        ));
+    }
+
+    #[test]
+    fn rejects_other_event_before_session_configured() {
+        let mut session_loop = SessionLoop::new();
+        let err = session_loop
+            .on_client_event(&ClientEvent::Other)
+            .expect_err("events before session_configured must be rejected");
+        assert!(matches!(err, CodexErr::SessionConfiguredNotFirstEvent));
+    }
+}
@@ -0,0 +1,139 @@
+use std::future::Future;
+
+use crate::error::CodexErr;
+use crate::error::Result;
+use crate::retry_scheduler::RetryDecision;
+use crate::retry_scheduler::UsageLimitRetryScheduler;
+
+/// Runs `run_turn` and, on `CodexErr::UsageLimitReached`, consults
+/// `scheduler` to decide whether to park the turn and automatically
+/// resubmit it once the usage window resets. Only usage-limit errors are
+/// retried here; every other error, `CodexErr::Stream(_, Option<Duration>)`
+/// included, is returned to the caller unchanged.
+///
+/// `cancel` is called to produce a fresh cancellation future each time a
+/// retry is parked; if it resolves while the wait is pending (e.g. the user
+/// pressed Ctrl-C), the wait ends with `CodexErr::Interrupted` instead of
+/// silently continuing to retry.
+pub async fn run_turn_with_usage_limit_retry<F, Fut, MakeCancel, Cancel>(
+    scheduler: &UsageLimitRetryScheduler,
+    mut run_turn: F,
+    mut cancel: MakeCancel,
+) -> Result<()>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<()>>,
+    MakeCancel: FnMut() -> Cancel,
+    Cancel: Future<Output = ()>,
+{
+    loop {
+        match run_turn().await {
+            Ok(()) => return Ok(()),
+            Err(CodexErr::UsageLimitReached(err)) => match scheduler.decide(&err) {
+                RetryDecision::RetryAfter(wait) => {
+                    UsageLimitRetryScheduler::wait_or_cancel(wait, cancel()).await?;
+                }
+                RetryDecision::GiveUp => return Err(CodexErr::UsageLimitReached(err)),
+            },
+            Err(other) => return Err(other),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::UsageLimitReachedError;
+    use crate::retry_scheduler::UsageLimitRetryConfig;
+    use std::sync::atomic::AtomicUsize;
+    use std::sync::atomic::Ordering;
+    use std::time::Duration;
+
+    fn usage_limit_err(resets_in_seconds: Option<u64>) -> CodexErr {
+        CodexErr::UsageLimitReached(UsageLimitReachedError {
+            plan_type: None,
+            resets_in_seconds,
+        })
+    }
+
+    #[tokio::test]
+    async fn retries_once_then_succeeds() {
+        let scheduler = UsageLimitRetryScheduler::new(UsageLimitRetryConfig {
+            enabled: true,
+            max_wait: Some(Duration::from_secs(1)),
+        });
+        let attempts = AtomicUsize::new(0);
+
+        let result = run_turn_with_usage_limit_retry(
+            &scheduler,
+            || async {
+                if attempts.fetch_add(1, Ordering::SeqCst) == 0 {
+                    Err(usage_limit_err(Some(0)))
+                } else {
+                    Ok(())
+                }
+            },
+            || std::future::pending(),
+        )
+        .await;
+
+        assert!(result.is_ok());
+        assert_eq!(attempts.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn disabled_scheduler_propagates_the_original_error_immediately() {
+        let scheduler = UsageLimitRetryScheduler::new(UsageLimitRetryConfig {
+            enabled: false,
+            max_wait: None,
+        });
+        let attempts = AtomicUsize::new(0);
+
+        let result = run_turn_with_usage_limit_retry(
+            &scheduler,
+            || async {
+                attempts.fetch_add(1, Ordering::SeqCst);
+                Err(usage_limit_err(Some(60)))
+            },
+            || std::future::pending(),
+        )
+        .await;
+
+        assert!(matches!(result, Err(CodexErr::UsageLimitReached(_))));
+        assert_eq!(attempts.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn cancelling_a_parked_retry_resolves_to_interrupted() {
+        let scheduler = UsageLimitRetryScheduler::new(UsageLimitRetryConfig {
+            enabled: true,
+            max_wait: Some(Duration::from_secs(3600)),
+        });
+
+        let result = run_turn_with_usage_limit_retry(
+            &scheduler,
+            || async { Err(usage_limit_err(Some(60))) },
+            || async {},
+        )
+        .await;
+
+        assert!(matches!(result, Err(CodexErr::Interrupted)));
+    }
+
+    #[tokio::test]
+    async fn non_usage_limit_errors_are_not_retried() {
+        let scheduler = UsageLimitRetryScheduler::new(UsageLimitRetryConfig {
+            enabled: true,
+            max_wait: None,
+        });
+
+        let result = run_turn_with_usage_limit_retry(
+            &scheduler,
+            || async { Err(CodexErr::InternalAgentDied) },
+            || std::future::pending(),
+        )
+        .await;
+
+        assert!(matches!(result, Err(CodexErr::InternalAgentDied)));
+    }
+}
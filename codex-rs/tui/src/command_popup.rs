@@ -0,0 +1,150 @@
+use crate::custom_command::CustomCommand;
+use crate::slash_command::all_command_entries;
+use crate::slash_command::CommandEntry;
+use crate::slash_command::SlashCommand;
+
+/// What committing the popup's current selection should do. Built-ins and
+/// custom commands both resolve to one of these, so the caller dispatches
+/// on `CommandAction` instead of matching on `SlashCommand` directly.
+#[derive(Debug, Clone)]
+pub enum CommandAction {
+    /// Hand off to the existing built-in command handling.
+    Builtin(SlashCommand),
+    /// Submit `prompt` as if the user had typed it — a custom command's
+    /// body with its `$1`/`$ARGS` placeholders already expanded.
+    SubmitPrompt(String),
+}
+
+/// Backs the `/` command popup: the merged built-in + custom command list
+/// (see `all_command_entries`), filtered by whatever the user has typed
+/// after `/` so far, with a highlighted row that arrow keys move.
+pub struct CommandPopup {
+    entries: Vec<(String, CommandEntry)>,
+    filtered: Vec<usize>,
+    selected: usize,
+}
+
+impl CommandPopup {
+    /// Build the popup over built-in commands merged with `custom_commands`
+    /// loaded from config, showing everything until `set_query` narrows it.
+    pub fn new(custom_commands: Vec<CustomCommand>) -> Self {
+        let entries = all_command_entries(custom_commands);
+        let filtered = (0..entries.len()).collect();
+        Self {
+            entries,
+            filtered,
+            selected: 0,
+        }
+    }
+
+    /// Recompute which entries match `query`, the text typed after `/` so
+    /// far, resetting the highlighted row to the top match.
+    pub fn set_query(&mut self, query: &str) {
+        self.filtered = self
+            .entries
+            .iter()
+            .enumerate()
+            .filter(|(_, (name, _))| name.starts_with(query))
+            .map(|(idx, _)| idx)
+            .collect();
+        self.selected = 0;
+    }
+
+    /// Move the highlighted row by `delta`, wrapping around. A negative
+    /// `delta` moves up.
+    pub fn move_selection(&mut self, delta: isize) {
+        if self.filtered.is_empty() {
+            return;
+        }
+        let len = self.filtered.len() as isize;
+        let next = (self.selected as isize + delta).rem_euclid(len);
+        self.selected = next as usize;
+    }
+
+    /// The entries currently visible in the popup, in display order.
+    pub fn visible(&self) -> impl Iterator<Item = &(String, CommandEntry)> {
+        self.filtered.iter().map(move |&idx| &self.entries[idx])
+    }
+
+    /// Resolve the highlighted row into the action selecting it should
+    /// perform. Returns `None` when the popup has no visible entries (the
+    /// query matched nothing), or when a task is in progress and the
+    /// highlighted entry isn't `available_during_task`.
+    pub fn select(&self, task_in_progress: bool, args: &[String]) -> Option<CommandAction> {
+        let idx = *self.filtered.get(self.selected)?;
+        let (_, entry) = &self.entries[idx];
+        if task_in_progress && !entry.available_during_task() {
+            return None;
+        }
+        Some(match entry {
+            CommandEntry::Builtin(cmd) => CommandAction::Builtin(*cmd),
+            CommandEntry::Custom(custom) => CommandAction::SubmitPrompt(custom.expand(args)),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn greet_command() -> CustomCommand {
+        CustomCommand {
+            id: "greet".to_string(),
+            label: "Greet".to_string(),
+            description: "打个招呼".to_string(),
+            body: "echo hello $1".to_string(),
+            available_during_task: false,
+        }
+    }
+
+    #[test]
+    fn new_popup_lists_builtins_and_custom_commands_together() {
+        let popup = CommandPopup::new(vec![greet_command()]);
+        let names: Vec<&str> = popup.visible().map(|(name, _)| name.as_str()).collect();
+        assert!(names.contains(&"model"));
+        assert!(names.contains(&"greet"));
+    }
+
+    #[test]
+    fn set_query_filters_by_prefix() {
+        let mut popup = CommandPopup::new(vec![greet_command()]);
+        popup.set_query("gr");
+        let names: Vec<&str> = popup.visible().map(|(name, _)| name.as_str()).collect();
+        assert_eq!(names, vec!["greet"]);
+    }
+
+    #[test]
+    fn select_resolves_custom_command_to_an_expanded_prompt() {
+        let mut popup = CommandPopup::new(vec![greet_command()]);
+        popup.set_query("greet");
+        let action = popup
+            .select(false, &["world".to_string()])
+            .expect("greet should be selectable");
+        assert!(matches!(
+            action,
+            CommandAction::SubmitPrompt(prompt) if prompt == "echo hello world"
+        ));
+    }
+
+    #[test]
+    fn select_resolves_builtin_command() {
+        let mut popup = CommandPopup::new(Vec::new());
+        popup.set_query("model");
+        let action = popup.select(false, &[]).expect("model should be selectable");
+        assert!(matches!(action, CommandAction::Builtin(SlashCommand::Model)));
+    }
+
+    #[test]
+    fn select_refuses_unavailable_during_task_entries() {
+        let mut popup = CommandPopup::new(Vec::new());
+        popup.set_query("model");
+        assert!(popup.select(true, &[]).is_none());
+    }
+
+    #[test]
+    fn select_returns_none_when_query_matches_nothing() {
+        let mut popup = CommandPopup::new(Vec::new());
+        popup.set_query("does-not-exist");
+        assert!(popup.select(false, &[]).is_none());
+    }
+}
@@ -0,0 +1,127 @@
+use std::collections::HashSet;
+
+use serde::Deserialize;
+
+/// A user-defined slash command loaded from the Codex config, as a parallel
+/// registry alongside the built-in [`crate::slash_command::SlashCommand`]
+/// set. Configured under a `[[custom_commands]]` table.
+#[derive(Debug, Clone, Deserialize)]
+pub struct CustomCommand {
+    /// Stable identifier for the command; also the string typed after the
+    /// leading `/` to invoke it.
+    pub id: String,
+    /// Display label shown in the command popup.
+    pub label: String,
+    /// Short human description shown next to the label in the popup.
+    pub description: String,
+    /// Prompt template or shell command to run. `$1`, `$2`, ... are replaced
+    /// with positional arguments and `$ARGS` with all of them space-joined.
+    pub body: String,
+    /// Whether this command can be run while a task is in progress.
+    #[serde(default)]
+    pub available_during_task: bool,
+}
+
+impl CustomCommand {
+    /// Substitute `$1`, `$2`, ... and `$ARGS` placeholders in `body` with
+    /// `args`, the whitespace-split argument list the user typed after the
+    /// command name.
+    pub fn expand(&self, args: &[String]) -> String {
+        let mut expanded = self.body.replace("$ARGS", &args.join(" "));
+        // Replace from the highest index down so `$1` doesn't get applied
+        // to a prefix of `$10`, `$11`, etc. before those get a chance to
+        // match in full.
+        for (idx, arg) in args.iter().enumerate().rev() {
+            expanded = expanded.replace(&format!("${}", idx + 1), arg);
+        }
+        expanded
+    }
+}
+
+/// Validate config-provided custom commands, dropping (and logging) entries
+/// with a blank id or body rather than failing the whole load.
+pub fn load_custom_commands(entries: Vec<CustomCommand>) -> Vec<CustomCommand> {
+    let mut seen_ids = HashSet::new();
+    entries
+        .into_iter()
+        .filter(|entry| {
+            if entry.id.trim().is_empty() || entry.body.trim().is_empty() {
+                tracing::warn!("忽略无效的自定义命令配置: {:?}", entry.id);
+                return false;
+            }
+            if !seen_ids.insert(entry.id.clone()) {
+                tracing::warn!("自定义命令 id 重复，已忽略: {}", entry.id);
+                return false;
+            }
+            true
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn expand_substitutes_positional_and_args() {
+        let cmd = CustomCommand {
+            id: "greet".to_string(),
+            label: "Greet".to_string(),
+            description: "打个招呼".to_string(),
+            body: "echo hello $1, all args: $ARGS".to_string(),
+            available_during_task: false,
+        };
+        let args = vec!["world".to_string(), "again".to_string()];
+        assert_eq!(cmd.expand(&args), "echo hello world, all args: world again");
+    }
+
+    #[test]
+    fn expand_does_not_let_dollar_one_shadow_dollar_ten() {
+        let cmd = CustomCommand {
+            id: "count".to_string(),
+            label: "Count".to_string(),
+            description: "".to_string(),
+            body: "$10".to_string(),
+            available_during_task: false,
+        };
+        let args = [
+            "ONE", "TWO", "THREE", "FOUR", "FIVE", "SIX", "SEVEN", "EIGHT", "NINE", "TEN",
+        ]
+        .into_iter()
+        .map(str::to_string)
+        .collect::<Vec<_>>();
+
+        assert_eq!(cmd.expand(&args), "TEN");
+    }
+
+    #[test]
+    fn load_custom_commands_drops_invalid_and_duplicate_entries() {
+        let entries = vec![
+            CustomCommand {
+                id: "ok".to_string(),
+                label: "Ok".to_string(),
+                description: "".to_string(),
+                body: "echo ok".to_string(),
+                available_during_task: false,
+            },
+            CustomCommand {
+                id: "".to_string(),
+                label: "Blank id".to_string(),
+                description: "".to_string(),
+                body: "echo blank".to_string(),
+                available_during_task: false,
+            },
+            CustomCommand {
+                id: "ok".to_string(),
+                label: "Duplicate".to_string(),
+                description: "".to_string(),
+                body: "echo dup".to_string(),
+                available_during_task: false,
+            },
+        ];
+
+        let loaded = load_custom_commands(entries);
+        assert_eq!(loaded.len(), 1);
+        assert_eq!(loaded[0].id, "ok");
+    }
+}
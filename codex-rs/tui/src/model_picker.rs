@@ -0,0 +1,96 @@
+use codex_common::model_presets::load_model_presets;
+use codex_common::model_presets::ModelPreset;
+use codex_common::model_presets::ModelPresetConfig;
+use codex_common::model_presets::ModelPresetConfigError;
+
+/// Backs the `/model` picker: the merged built-in + config-provided preset
+/// list, plus whatever validation errors config entries failed with, so the
+/// TUI can surface them instead of silently dropping bad config.
+pub struct ModelPicker {
+    presets: Vec<ModelPreset>,
+    pub config_errors: Vec<ModelPresetConfigError>,
+    selected: usize,
+}
+
+impl ModelPicker {
+    /// Load the picker's entries: built-ins merged with `custom_presets`
+    /// read from the Codex config, built-ins first, with a custom entry
+    /// overriding a built-in in place when their ids match.
+    pub fn new(custom_presets: Vec<ModelPresetConfig>) -> Self {
+        let (presets, config_errors) = load_model_presets(custom_presets);
+        Self {
+            presets,
+            config_errors,
+            selected: 0,
+        }
+    }
+
+    /// All presets the picker currently lists, in display order.
+    pub fn presets(&self) -> &[ModelPreset] {
+        &self.presets
+    }
+
+    pub fn move_selection(&mut self, delta: isize) {
+        if self.presets.is_empty() {
+            return;
+        }
+        let len = self.presets.len() as isize;
+        let next = (self.selected as isize + delta).rem_euclid(len);
+        self.selected = next as usize;
+    }
+
+    /// The currently highlighted preset, `None` if the list is empty.
+    pub fn selected(&self) -> Option<&ModelPreset> {
+        self.presets.get(self.selected)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn defaults_to_the_first_builtin_preset() {
+        let picker = ModelPicker::new(Vec::new());
+        assert_eq!(
+            picker.selected().map(|p| p.id.as_str()),
+            Some("gpt-5-minimal")
+        );
+    }
+
+    #[test]
+    fn surfaces_custom_preset_and_config_errors_separately() {
+        let picker = ModelPicker::new(vec![
+            ModelPresetConfig {
+                id: "my-model-medium".to_string(),
+                label: "my-model medium".to_string(),
+                description: String::new(),
+                model: "my-model".to_string(),
+                effort: "medium".to_string(),
+            },
+            ModelPresetConfig {
+                id: "bad".to_string(),
+                label: "Bad".to_string(),
+                description: String::new(),
+                model: "my-model".to_string(),
+                effort: "extreme".to_string(),
+            },
+        ]);
+
+        assert!(picker
+            .presets()
+            .iter()
+            .any(|p| p.id == "my-model-medium"));
+        assert_eq!(picker.config_errors.len(), 1);
+    }
+
+    #[test]
+    fn move_selection_wraps_around() {
+        let mut picker = ModelPicker::new(Vec::new());
+        let last_id = picker.presets().last().unwrap().id.clone();
+
+        // Moving back from the first entry should wrap to the last one.
+        picker.move_selection(-1);
+        assert_eq!(picker.selected().map(|p| p.id.clone()), Some(last_id));
+    }
+}
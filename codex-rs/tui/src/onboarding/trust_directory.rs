@@ -24,6 +24,10 @@ pub(crate) struct TrustDirectoryWidget {
     pub codex_home: PathBuf,
     pub cwd: PathBuf,
     pub is_git_repo: bool,
+    /// True when the active sandbox policy targets a remote host (e.g.
+    /// `SandboxPolicy::RemoteSsh`). Remote hosts are never implicitly
+    /// trusted, regardless of whether `cwd` is version-controlled.
+    pub is_remote: bool,
     pub selection: Option<TrustDirectorySelection>,
     pub highlighted: TrustDirectorySelection,
     pub error: Option<String>,
@@ -46,7 +50,10 @@ impl WidgetRef for &TrustDirectoryWidget {
             "".into(),
         ];
 
-        if self.is_git_repo {
+        if self.is_remote {
+            lines.push("  此命令将在远程主机上执行，远程主机不会被".into());
+            lines.push("  自动信任，所有编辑与命令都需要审批。".into());
+        } else if self.is_git_repo {
             lines.push("  此文件夹受版本控制，您可以选择允许 Codex".into());
             lines.push("  在该文件夹内无需审批即可工作。".into());
         } else {
@@ -65,7 +72,13 @@ impl WidgetRef for &TrustDirectoryWidget {
                 }
             };
 
-        if self.is_git_repo {
+        if self.is_remote {
+            lines.push(create_option(
+                0,
+                TrustDirectorySelection::DontTrust,
+                "需我审批编辑与命令",
+            ));
+        } else if self.is_git_repo {
             lines.push(create_option(
                 0,
                 TrustDirectorySelection::Trust,
@@ -134,6 +147,13 @@ impl StepStateProvider for TrustDirectoryWidget {
 
 impl TrustDirectoryWidget {
     fn handle_trust(&mut self) {
+        if self.is_remote {
+            // Remote hosts are never implicitly trusted; fall back to
+            // requiring approval instead of silently trusting the cwd.
+            self.handle_dont_trust();
+            return;
+        }
+
         let target =
             resolve_root_git_project_for_trust(&self.cwd).unwrap_or_else(|| self.cwd.clone());
         if let Err(e) = set_project_trusted(&self.codex_home, &target) {
@@ -0,0 +1,165 @@
+use std::io::Read;
+use std::io::Write;
+
+use codex_core::error::CodexErr;
+use codex_core::error::Result;
+use codex_core::exec::network_access_allowed;
+use codex_core::protocol::SandboxPolicy;
+use portable_pty::native_pty_system;
+use portable_pty::Child;
+use portable_pty::CommandBuilder;
+use portable_pty::PtySize;
+
+/// Backs `SlashCommand::Shell`: an interactive pseudo-terminal session
+/// running inside the active `SandboxPolicy`. Keystrokes the TUI reads from
+/// the user are written to `write_input`; bytes read back from
+/// `read_output` are the child's live terminal output and should be rendered
+/// as they arrive rather than buffered until exit.
+pub struct ShellSession {
+    writer: Box<dyn Write + Send>,
+    reader: Box<dyn Read + Send>,
+    child: Box<dyn Child + Send + Sync>,
+}
+
+impl ShellSession {
+    /// Spawn the user's shell (`$SHELL`, falling back to `/bin/sh`) attached
+    /// to a `cols` x `rows` PTY.
+    ///
+    /// Any failure to allocate the PTY or spawn the child surfaces as
+    /// `CodexErr::Spawn`, the same error `run_command_stream` already uses
+    /// when a sandboxed command's stdout/stderr pipes can't be captured.
+    /// Also returns `CodexErr::Spawn` if `sandbox` doesn't allow an
+    /// interactive shell at all (see `shell_is_allowed_under`).
+    pub fn spawn(sandbox: &SandboxPolicy, cols: u16, rows: u16) -> Result<Self> {
+        if !shell_is_allowed_under(sandbox) {
+            return Err(CodexErr::Spawn);
+        }
+
+        let pty_system = native_pty_system();
+        let pair = pty_system
+            .openpty(PtySize {
+                rows,
+                cols,
+                pixel_width: 0,
+                pixel_height: 0,
+            })
+            .map_err(|_| CodexErr::Spawn)?;
+
+        let shell = std::env::var("SHELL").unwrap_or_else(|_| "/bin/sh".to_string());
+        let mut cmd = CommandBuilder::new(shell);
+
+        // Take the network-access decision from `core::exec`'s
+        // `network_access_allowed` rather than re-deriving it here; it gives
+        // the same answer `apply_sandbox_policy` applies to regular commands.
+        if !network_access_allowed(sandbox) {
+            cmd.env("CODEX_SANDBOX_NETWORK_DISABLED", "1");
+        }
+
+        let child = pair
+            .slave
+            .spawn_command(cmd)
+            .map_err(|_| CodexErr::Spawn)?;
+        drop(pair.slave);
+
+        let writer = pair.master.take_writer().map_err(|_| CodexErr::Spawn)?;
+        let reader = pair
+            .master
+            .try_clone_reader()
+            .map_err(|_| CodexErr::Spawn)?;
+
+        Ok(Self {
+            writer,
+            reader,
+            child,
+        })
+    }
+
+    /// Forward a keystroke (or any raw bytes, e.g. a paste) typed in the TUI
+    /// to the child's stdin.
+    pub fn write_input(&mut self, bytes: &[u8]) -> std::io::Result<()> {
+        self.writer.write_all(bytes)
+    }
+
+    /// Read whatever output the child has produced since the last call, so
+    /// it can be rendered live. Returns `Ok(0)` once the child has exited
+    /// and no more output remains.
+    pub fn read_output(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        self.reader.read(buf)
+    }
+
+    /// Whether the child has exited.
+    pub fn has_exited(&mut self) -> bool {
+        matches!(self.child.try_wait(), Ok(Some(_)))
+    }
+}
+
+/// Whether `sandbox` permits starting an interactive shell at all.
+///
+/// Unlike a single command, a whole shell session can't have its
+/// filesystem writes contained by the kernel-level sandboxing
+/// (`SandboxErr::SeccompInstall` / `SandboxErr::LandlockRestrict`) that
+/// regular commands get: `portable_pty`'s cross-platform `CommandBuilder`
+/// doesn't expose a `pre_exec` hook to install that sandboxing before the
+/// shell execs. Until that lands, refusing to start under `ReadOnly` is
+/// safer than handing out a shell that silently ignores the policy.
+///
+/// `RemoteSsh` is refused for a different reason: `ShellSession` always
+/// spawns `$SHELL` locally via `native_pty_system()`, so a local PTY would
+/// hand out full local filesystem access under a policy that says all
+/// command execution should be confined to the remote host instead. There
+/// is no remote-PTY path yet, so the honest answer is to refuse rather than
+/// silently run locally.
+fn shell_is_allowed_under(sandbox: &SandboxPolicy) -> bool {
+    !matches!(
+        sandbox,
+        SandboxPolicy::ReadOnly | SandboxPolicy::RemoteSsh { .. }
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn read_only_refuses_an_interactive_shell() {
+        assert!(!shell_is_allowed_under(&SandboxPolicy::ReadOnly));
+    }
+
+    #[test]
+    fn remote_ssh_refuses_an_interactive_shell() {
+        assert!(!shell_is_allowed_under(&SandboxPolicy::RemoteSsh {
+            host: "example.com".to_string(),
+            port: 22,
+            user: "codex".to_string(),
+            remote_cwd: std::path::PathBuf::from("/home/codex"),
+        }));
+    }
+
+    #[test]
+    fn workspace_write_and_full_access_allow_an_interactive_shell() {
+        assert!(shell_is_allowed_under(&SandboxPolicy::WorkspaceWrite {
+            network_access: false
+        }));
+        assert!(shell_is_allowed_under(&SandboxPolicy::DangerFullAccess));
+    }
+
+    #[test]
+    fn spawn_under_read_only_returns_codex_spawn_error() {
+        let err = ShellSession::spawn(&SandboxPolicy::ReadOnly, 80, 24)
+            .expect_err("ReadOnly must refuse to spawn an interactive shell");
+        assert!(matches!(err, CodexErr::Spawn));
+    }
+
+    #[test]
+    fn spawn_under_remote_ssh_returns_codex_spawn_error() {
+        let sandbox = SandboxPolicy::RemoteSsh {
+            host: "example.com".to_string(),
+            port: 22,
+            user: "codex".to_string(),
+            remote_cwd: std::path::PathBuf::from("/home/codex"),
+        };
+        let err = ShellSession::spawn(&sandbox, 80, 24)
+            .expect_err("RemoteSsh must refuse a local interactive shell");
+        assert!(matches!(err, CodexErr::Spawn));
+    }
+}
@@ -0,0 +1,133 @@
+use codex_core::error::CodexErr;
+use codex_core::protocol::SandboxPolicy;
+use crossterm::event::KeyCode;
+use crossterm::event::KeyEvent;
+use crossterm::event::KeyModifiers;
+use ratatui::buffer::Buffer;
+use ratatui::layout::Rect;
+use ratatui::prelude::Widget;
+use ratatui::style::Stylize;
+use ratatui::text::Line;
+use ratatui::widgets::Paragraph;
+use ratatui::widgets::WidgetRef;
+use ratatui::widgets::Wrap;
+
+use crate::onboarding::onboarding_screen::KeyboardHandler;
+use crate::shell_session::ShellSession;
+
+/// Renders and drives `/shell`'s interactive PTY session in the TUI: key
+/// events the user types are forwarded to the child, and whatever the
+/// child has written since the last poll is appended to what's rendered.
+pub struct ShellWidget {
+    session: Option<ShellSession>,
+    /// Raw bytes the child has written so far. A real terminal emulator
+    /// (cursor movement, colors, etc.) would interpret escape sequences
+    /// here instead of rendering them as literal lines; that's tracked as
+    /// a follow-up, not something this widget needs to solve to forward
+    /// keystrokes and display output live.
+    output: Vec<u8>,
+    spawn_error: Option<String>,
+    exited: bool,
+}
+
+impl ShellWidget {
+    pub fn new(sandbox: &SandboxPolicy, cols: u16, rows: u16) -> Self {
+        match ShellSession::spawn(sandbox, cols, rows) {
+            Ok(session) => Self {
+                session: Some(session),
+                output: Vec::new(),
+                spawn_error: None,
+                exited: false,
+            },
+            Err(err) => Self {
+                session: None,
+                output: Vec::new(),
+                spawn_error: Some(describe_spawn_error(&err)),
+                exited: true,
+            },
+        }
+    }
+
+    /// Drain whatever output the child has produced since the last call.
+    /// The TUI's render loop calls this on every tick before rendering.
+    pub fn poll_output(&mut self) {
+        let Some(session) = self.session.as_mut() else {
+            return;
+        };
+
+        let mut buf = [0u8; 4096];
+        loop {
+            match session.read_output(&mut buf) {
+                Ok(0) => break,
+                Ok(n) => self.output.extend_from_slice(&buf[..n]),
+                Err(_) => break,
+            }
+        }
+
+        if session.has_exited() {
+            self.exited = true;
+        }
+    }
+
+    pub fn has_exited(&self) -> bool {
+        self.exited
+    }
+}
+
+fn describe_spawn_error(err: &CodexErr) -> String {
+    match err {
+        CodexErr::Spawn => "无法启动交互式 shell（可能是当前沙箱策略不允许）".to_string(),
+        other => other.to_string(),
+    }
+}
+
+impl KeyboardHandler for ShellWidget {
+    fn handle_key_event(&mut self, key_event: KeyEvent) {
+        let Some(session) = self.session.as_mut() else {
+            return;
+        };
+
+        let bytes: Vec<u8> = match key_event.code {
+            KeyCode::Char(c) if key_event.modifiers.contains(KeyModifiers::CONTROL) => {
+                // Map Ctrl-<letter> to its control byte, e.g. Ctrl-C -> 0x03.
+                vec![(c.to_ascii_uppercase() as u8).wrapping_sub(b'A' - 1)]
+            }
+            KeyCode::Char(c) => c.to_string().into_bytes(),
+            KeyCode::Enter => vec![b'\r'],
+            KeyCode::Tab => vec![b'\t'],
+            KeyCode::Backspace => vec![0x7f],
+            KeyCode::Esc => vec![0x1b],
+            _ => return,
+        };
+
+        let _ = session.write_input(&bytes);
+    }
+}
+
+impl WidgetRef for &ShellWidget {
+    fn render_ref(&self, area: Rect, buf: &mut Buffer) {
+        if let Some(error) = &self.spawn_error {
+            Paragraph::new(Line::from(error.as_str()).red())
+                .wrap(Wrap { trim: false })
+                .render(area, buf);
+            return;
+        }
+
+        let text = String::from_utf8_lossy(&self.output);
+        Paragraph::new(text.to_string())
+            .wrap(Wrap { trim: false })
+            .render(area, buf);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn read_only_sandbox_surfaces_a_spawn_error_instead_of_a_session() {
+        let widget = ShellWidget::new(&SandboxPolicy::ReadOnly, 80, 24);
+        assert!(widget.spawn_error.is_some());
+        assert!(widget.has_exited());
+    }
+}
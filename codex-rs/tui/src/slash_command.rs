@@ -1,9 +1,13 @@
+use std::collections::HashSet;
+
 use strum::IntoEnumIterator;
 use strum_macros::AsRefStr;
 use strum_macros::EnumIter;
 use strum_macros::EnumString;
 use strum_macros::IntoStaticStr;
 
+use crate::custom_command::CustomCommand;
+
 /// Commands that can be invoked by starting a message with a leading slash.
 #[derive(
     Debug, Clone, Copy, PartialEq, Eq, Hash, EnumString, EnumIter, AsRefStr, IntoStaticStr,
@@ -22,6 +26,7 @@ pub enum SlashCommand {
     Status,
     Mcp,
     Logout,
+    Shell,
     Quit,
     #[cfg(debug_assertions)]
     TestApproval,
@@ -42,6 +47,10 @@ impl SlashCommand {
             SlashCommand::Approvals => "选择 Codex 可以在无需批准情况下执行的操作",
             SlashCommand::Mcp => "列出已配置的 MCP 工具",
             SlashCommand::Logout => "退出 Codex 登录",
+            // Opens a `crate::shell_widget::ShellWidget`, backed by a real
+            // PTY (`crate::shell_session::ShellSession`) running inside the
+            // current `SandboxPolicy`.
+            SlashCommand::Shell => "在当前沙箱策略下打开一个交互式 shell",
             #[cfg(debug_assertions)]
             SlashCommand::TestApproval => "（仅在调试模式下）测试审批请求",
         }
@@ -61,7 +70,8 @@ impl SlashCommand {
             | SlashCommand::Compact
             | SlashCommand::Model
             | SlashCommand::Approvals
-            | SlashCommand::Logout => false,
+            | SlashCommand::Logout
+            | SlashCommand::Shell => false,
             SlashCommand::Diff
             | SlashCommand::Mention
             | SlashCommand::Status
@@ -78,3 +88,51 @@ impl SlashCommand {
 pub fn built_in_slash_commands() -> Vec<(&'static str, SlashCommand)> {
     SlashCommand::iter().map(|c| (c.command(), c)).collect()
 }
+
+/// A single entry in the command popup: either a built-in command or a
+/// user-defined one loaded from the Codex config.
+#[derive(Debug, Clone)]
+pub enum CommandEntry {
+    Builtin(SlashCommand),
+    Custom(CustomCommand),
+}
+
+impl CommandEntry {
+    /// User-visible description shown in the popup.
+    pub fn description(&self) -> &str {
+        match self {
+            CommandEntry::Builtin(cmd) => cmd.description(),
+            CommandEntry::Custom(custom) => &custom.description,
+        }
+    }
+
+    /// Whether this command can be run while a task is in progress.
+    pub fn available_during_task(&self) -> bool {
+        match self {
+            CommandEntry::Builtin(cmd) => cmd.available_during_task(),
+            CommandEntry::Custom(custom) => custom.available_during_task,
+        }
+    }
+}
+
+/// Merge built-in commands with user-defined ones from config into the list
+/// the command popup iterates over, built-ins first. A custom command whose
+/// id collides with a built-in is dropped in favor of the built-in.
+pub fn all_command_entries(custom_commands: Vec<CustomCommand>) -> Vec<(String, CommandEntry)> {
+    let mut entries: Vec<(String, CommandEntry)> = built_in_slash_commands()
+        .into_iter()
+        .map(|(name, cmd)| (name.to_string(), CommandEntry::Builtin(cmd)))
+        .collect();
+
+    let builtin_ids: HashSet<String> = entries.iter().map(|(name, _)| name.clone()).collect();
+
+    for custom in custom_commands {
+        if builtin_ids.contains(custom.id.as_str()) {
+            tracing::warn!("自定义命令 `{}` 与内置命令同名，已忽略", custom.id);
+            continue;
+        }
+        entries.push((custom.id.clone(), CommandEntry::Custom(custom)));
+    }
+
+    entries
+}
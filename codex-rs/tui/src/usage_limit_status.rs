@@ -0,0 +1,65 @@
+use std::time::Duration;
+
+use codex_core::error::format_reset_duration;
+use ratatui::style::Stylize;
+use ratatui::text::Line;
+
+/// Status line shown while a turn is parked waiting for a usage-limit reset
+/// to elapse, per the opt-in `UsageLimitRetryScheduler`. Ticks down once a
+/// second; the TUI's render loop calls `tick` and then `line`.
+pub struct UsageLimitStatusLine {
+    remaining: Duration,
+}
+
+impl UsageLimitStatusLine {
+    /// Start a countdown for `wait`, the duration
+    /// `UsageLimitRetryScheduler::decide` returned.
+    pub fn new(wait: Duration) -> Self {
+        Self { remaining: wait }
+    }
+
+    /// Advance the countdown by `elapsed`, saturating at zero.
+    pub fn tick(&mut self, elapsed: Duration) {
+        self.remaining = self.remaining.saturating_sub(elapsed);
+    }
+
+    /// Whether the countdown has reached zero (the turn is about to retry).
+    pub fn is_done(&self) -> bool {
+        self.remaining.is_zero()
+    }
+
+    /// The status line to render, e.g. "已达到使用上限，3 分钟 后自动重试…".
+    pub fn line(&self) -> Line<'static> {
+        let remaining = format_reset_duration(self.remaining.as_secs());
+        Line::from(format!("已达到使用上限，{remaining} 后自动重试…")).dim()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tick_counts_down_and_reports_done_at_zero() {
+        let mut status = UsageLimitStatusLine::new(Duration::from_secs(5));
+        assert!(!status.is_done());
+
+        status.tick(Duration::from_secs(3));
+        assert!(!status.is_done());
+
+        status.tick(Duration::from_secs(10));
+        assert!(status.is_done());
+    }
+
+    #[test]
+    fn line_renders_the_remaining_time() {
+        let status = UsageLimitStatusLine::new(Duration::from_secs(5 * 60));
+        let rendered: String = status
+            .line()
+            .spans
+            .iter()
+            .map(|span| span.content.as_ref())
+            .collect();
+        assert_eq!(rendered, "已达到使用上限，5 分钟 后自动重试…");
+    }
+}